@@ -1,28 +1,197 @@
 #![allow(dead_code)]
-use std::collections::VecDeque;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use std::any::Any;
+use std::collections::{BinaryHeap, VecDeque};
+use std::cmp::Ordering as CmpOrdering;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
 use std::thread::JoinHandle;
 
 mod test;
 
+///
+/// Sent by a WorkerContext back to the owning BackgroundWorker, tagged with
+/// the index of the worker that sent it so status/progress can be reported
+/// per-worker instead of as one global "whoever reported last" value. Drained
+/// lazily by current_status()/current_progress() rather than by a dedicated
+/// thread
+///
+enum ContextMessage {
+    Status(usize, String),
+    Progress(usize, u64, u64),
+}
+
+///
+/// Handed to the user-provided function on every invocation of the
+/// `_with_context` constructors so it can report what it's doing without the
+/// caller having to drain the output queue. Cheap to hand out: it's just an
+/// index plus a clone of an mpsc sender
+///
+pub struct WorkerContext {
+    index: usize,
+    sender: mpsc::Sender<ContextMessage>,
+}
+
+impl WorkerContext {
+    ///
+    /// Reports what this worker is currently doing, e.g. "connecting",
+    /// "parsing batch 3". Visible to the caller via current_status()
+    ///
+    pub fn set_status(&self, status: &str) {
+        let _ = self.sender.send(ContextMessage::Status(self.index, status.to_string()));
+    }
+
+    ///
+    /// Reports how far through a long-running item this worker is. Visible to
+    /// the caller via current_progress()
+    ///
+    pub fn report_progress(&self, done: u64, total: u64) {
+        let _ = self.sender.send(ContextMessage::Progress(self.index, done, total));
+    }
+}
+
+///
+/// The uniform internal representation of a worker's function, after
+/// non-context constructors have adapted their closures to take a
+/// &WorkerContext. Aliased since this type appears in several signatures
+/// around worker construction and clippy::type_complexity objects to
+/// spelling it out each time
+///
+type WorkerFn<Input, Output> = Box<dyn FnMut(Input, &WorkerContext) -> Output + Send>;
+
+///
+/// The priority used by enque/enque_vec, i.e. "nothing special requested"
+///
+const DEFAULT_PRIORITY: u64 = 0;
+
+///
+/// Carries the payload of a panic caught from inside the user-provided
+/// function, stringified since panic payloads aren't required to be anything
+/// in particular (commonly `&str` or `String`)
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkerError {
+    pub message: String,
+}
+
+///
+/// Pulls a human-readable message out of a caught panic's payload
+///
+fn describe_panic(payload: Box<dyn Any + Send>) -> WorkerError {
+    let message = if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "worker function panicked with a non-string payload".to_string()
+    };
+
+    WorkerError { message }
+}
+
+///
+/// Returned by join(): how many items have completed successfully vs. panicked
+/// since this BackgroundWorker was created
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct JoinSummary {
+    pub completed: usize,
+    pub panicked: usize,
+}
+
+///
+/// A single queued input alongside its priority and the order it was enqueued
+/// in. Higher priority dequeues first; ties fall back to FIFO order via `seq`.
+/// This `seq` tiebreak is what makes new()/with_threads(func, 1) preserve
+/// the pre-existing FIFO single-thread API even though each worker's deque is
+/// a BinaryHeap, which by itself keeps no insertion order: every item enqueued
+/// via enque/enque_vec shares the same default priority, so `seq` alone
+/// decides the order
+///
+#[derive(Debug)]
+struct Entry<Input> {
+    priority: u64,
+    seq: u64,
+    value: Input,
+}
+
+impl<Input> PartialEq for Entry<Input> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.seq == other.seq
+    }
+}
+
+impl<Input> Eq for Entry<Input> {}
+
+impl<Input> PartialOrd for Entry<Input> {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<Input> Ord for Entry<Input> {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+///
+/// Everything a worker thread needs that is shared across every worker,
+/// bundled up so spawn_worker takes one argument instead of growing a new
+/// positional parameter every time a feature needs another shared Arc
+///
+struct WorkerShared<Input, Output> {
+    deques: Vec<Arc<Mutex<BinaryHeap<Entry<Input>>>>>,
+    outqueue: Arc<Mutex<VecDeque<Result<Output, WorkerError>>>>,
+    outqueue_cvar: Arc<Condvar>,
+    wake_lock: Arc<Mutex<()>>,
+    wake_cvar: Arc<Condvar>,
+    queued: Arc<AtomicUsize>,
+    pending: Arc<AtomicUsize>,
+    completed: Arc<AtomicUsize>,
+    panicked: Arc<AtomicUsize>,
+    stop: Arc<AtomicBool>,
+    context_tx: mpsc::Sender<ContextMessage>,
+}
+
+impl<Input, Output> Clone for WorkerShared<Input, Output> {
+    fn clone(&self) -> Self {
+        WorkerShared {
+            deques: self.deques.clone(),
+            outqueue: self.outqueue.clone(),
+            outqueue_cvar: self.outqueue_cvar.clone(),
+            wake_lock: self.wake_lock.clone(),
+            wake_cvar: self.wake_cvar.clone(),
+            queued: self.queued.clone(),
+            pending: self.pending.clone(),
+            completed: self.completed.clone(),
+            panicked: self.panicked.clone(),
+            stop: self.stop.clone(),
+            context_tx: self.context_tx.clone(),
+        }
+    }
+}
+
 ///
 /// A struct that performs a lengthy task in a seperate thread with a queue
 /// Types:
-/// 
+///
 /// Input: 'static
 ///     Input type
 ///     Must be statically specified (ie. BackgroundWorker<i32, f32>)
 ///     Must derive: std::marker::Send      --To send between threads
-/// 
+///
 /// Output: 'static
 ///     Output type
 ///     Must be statically specified (ie. BackgroundWorker<i32, f32>)
 ///     Must derive: std::marker::Send,     --To send between threads
 ///                  std::clone::Clone,     --To be able to clone in the pop_range function
 ///                  std::cmp::PartialEq
-/// 
+///
 
 #[derive(Debug)]
 struct BackgroundWorker<Input: 'static, Output: 'static>
@@ -31,27 +200,111 @@ where
     Output: std::marker::Send + std::clone::Clone + std::cmp::PartialEq,
 {
     ///
-    /// A user-provided function that takes in input and produces output
-    /// 
-    function: fn(Input) -> Output,
+    /// The long-lived JoinHandle for each worker thread, indexed the same as
+    /// deques. Workers are spawned once in with_threads and live until shutdown()
     ///
-    /// A worker-created JoinHandle that starts when something is queued
-    /// 
-    thread_handle: Option<JoinHandle<()>>,
+    thread_handles: Vec<JoinHandle<()>>,
     ///
-    /// outqueue/inqueue:
-    ///     A public VecDequeue<Output> which is wrapped in a Mutex and an Arc
+    /// outqueue:
+    ///     A public VecDequeue<Result<Output, WorkerError>> which is wrapped in
+    ///     a Mutex and an Arc. Holds Err(WorkerError) for any item whose
+    ///     invocation of `function` panicked, so a panic can't silently drop
+    ///     the item or take down the caller when they eventually join()/pop()
     ///     Only made public to allow for more precise use of the queues, not
     ///     reccomended to be used in any general circumstance, use the pop and
     ///     push and their respective _vec variants for putting stuff in and out
-    /// 
-    pub outqueue: Arc<Mutex<VecDeque<Output>>>,
-    pub inqueue: Arc<Mutex<VecDeque<Input>>>,
-    ///
-    /// An atomic boolean that is set to true when new() is called or when a
-    /// thread ends, otherwise false
-    /// 
-    thread_dead: Arc<AtomicBool>,
+    ///
+    pub outqueue: Arc<Mutex<VecDeque<Result<Output, WorkerError>>>>,
+    ///
+    /// Notified whenever a result is pushed to outqueue, so pop_blocking doesn't
+    /// have to busy-poll
+    ///
+    outqueue_cvar: Arc<Condvar>,
+    ///
+    /// deques:
+    ///     One priority queue per worker thread, keyed on the priority passed to
+    ///     enque_with_priority (highest first, FIFO among equal priorities). A
+    ///     worker pops its own highest-priority item first and, when that is
+    ///     empty, steals the highest-priority item from a sibling instead of
+    ///     idling. Ordering is only a hint: several items may already be in
+    ///     flight on other workers regardless of the priority of what's left
+    ///
+    pub deques: Vec<Arc<Mutex<BinaryHeap<Entry<Input>>>>>,
+    ///
+    /// Paired with wake_cvar: the lock a worker holds while deciding whether to
+    /// wait, so an enque's "bump queued, then notify" can never race a worker's
+    /// "check queued, then wait" into a missed wakeup
+    ///
+    wake_lock: Arc<Mutex<()>>,
+    ///
+    /// Notified by enque/enque_vec after pushing so an idle worker parked in
+    /// spawn_worker's loop wakes up instead of being respawned
+    ///
+    wake_cvar: Arc<Condvar>,
+    ///
+    /// Number of items currently sitting in a deque (pushed but not yet popped
+    /// by any worker). Distinct from `pending` below: a worker only blocks on
+    /// wake_cvar when this is zero, even if other items are still in flight
+    ///
+    queued: Arc<AtomicUsize>,
+    ///
+    /// The number of items that are either still queued or currently being
+    /// processed by a worker. join() blocks until this reaches zero instead
+    /// of relying on a single JoinHandle
+    ///
+    pending: Arc<AtomicUsize>,
+    ///
+    /// Lifetime count of items whose invocation of `function` returned
+    /// normally, reported back by join()
+    ///
+    completed: Arc<AtomicUsize>,
+    ///
+    /// Lifetime count of items whose invocation of `function` panicked,
+    /// reported back by join()
+    ///
+    panicked: Arc<AtomicUsize>,
+    ///
+    /// Index of the deque that the next enque/enque_vec push will round-robin to
+    ///
+    next_push: AtomicUsize,
+    ///
+    /// Monotonically increasing counter stamped onto every enqueued Entry so
+    /// that equal priorities still dequeue in the order they were submitted
+    ///
+    next_seq: u64,
+    ///
+    /// Set by shutdown() to tell idle workers to exit instead of waiting for
+    /// more work. Workers still drain whatever is already queued first
+    ///
+    stop: Arc<AtomicBool>,
+    ///
+    /// Set once shutdown() has joined every worker thread. pop_blocking
+    /// consumers should treat this, not `stop`, as "the worker has shut down",
+    /// since `stop` alone doesn't guarantee the last in-flight results have
+    /// been pushed to outqueue yet
+    ///
+    shutdown_complete: Arc<AtomicBool>,
+    ///
+    /// Receiving half of the WorkerContext channel. Wrapped in a Mutex purely
+    /// so current_status/current_progress can drain it through a shared &self
+    ///
+    context_rx: Arc<Mutex<mpsc::Receiver<ContextMessage>>>,
+    ///
+    /// Cloned into every WorkerContext handed to `function`
+    ///
+    context_tx: mpsc::Sender<ContextMessage>,
+    ///
+    /// Most recent status string reported by each worker (indexed the same as
+    /// deques/thread_handles), cached here since context_rx can only be
+    /// drained once. Exposed per-worker via current_status() since a single
+    /// shared slot would have workers stomp on each other's reports
+    ///
+    latest_status: Mutex<Vec<Option<String>>>,
+    ///
+    /// Most recent (done, total) progress pair reported by each worker,
+    /// exposed per-worker via current_progress() for the same reason
+    ///
+    latest_progress: Mutex<Vec<Option<(u64, u64)>>>,
 }
 
 impl<Input: 'static, Output: 'static> BackgroundWorker<Input, Output>
@@ -60,42 +313,286 @@ where
     Output: std::marker::Send + std::clone::Clone + std::cmp::PartialEq,
 {
     ///
-    /// Creates a BackgroundWorker with the specified function
+    /// Creates a BackgroundWorker with the specified function, backed by a
+    /// single worker thread
     /// Parameters:
     ///     func:
     ///         A function pointer (can also be a closure) which takes in an input
-    ///         and produces an output. Write this function assuming it will run 
-    ///         on a seperate thread rather than the main thread. 
-    /// 
+    ///         and produces an output. Write this function assuming it will run
+    ///         on a seperate thread rather than the main thread.
+    ///
     pub fn new(func: fn(Input) -> Output) -> BackgroundWorker<Input, Output> {
-        BackgroundWorker {
-            function: func,
+        BackgroundWorker::new_with(func)
+    }
+
+    ///
+    /// Same as new, but accepts any stateful `FnMut` closure instead of a bare
+    /// function pointer, so a worker can hold a connection, a cache, or other
+    /// configuration captured from its environment. Unlike with_threads, `func`
+    /// is not required to be `Clone`: there's only ever one worker here, so it
+    /// is moved into that worker's thread exactly once and never cloned
+    /// Parameters:
+    ///     func:
+    ///         A closure which takes in an input and produces an output. Write
+    ///         it assuming it will run on a seperate thread rather than the
+    ///         main thread
+    ///
+    pub fn new_with<F>(mut func: F) -> BackgroundWorker<Input, Output>
+    where
+        F: FnMut(Input) -> Output + Send + 'static,
+    {
+        BackgroundWorker::new_with_context(move |value, _ctx: &WorkerContext| func(value))
+    }
+
+    ///
+    /// Same as new_with, but `func` also receives a &WorkerContext so it can
+    /// call set_status/report_progress to surface what it's doing without the
+    /// caller having to drain the output queue (see current_status,
+    /// current_progress)
+    ///
+    pub fn new_with_context<F>(func: F) -> BackgroundWorker<Input, Output>
+    where
+        F: FnMut(Input, &WorkerContext) -> Output + Send + 'static,
+    {
+        Self::build_from_funcs(vec![Box::new(func)], None)
+    }
+
+    ///
+    /// Creates a BackgroundWorker backed by `num_threads` persistent worker
+    /// threads that share the work via stealing, which matters for CPU-bound
+    /// functions where a single worker would leave cores idle. Unlike the old
+    /// spawn-on-enque design, all `num_threads` workers are started here and
+    /// stay parked on a Condvar between bursts of work instead of dying and
+    /// paying full thread-spawn cost again on the next enque
+    ///
+    /// `func` is cloned once per worker and moved into that worker's thread at
+    /// construction time (never re-cloned per spawn), so each worker gets its
+    /// own independent mutable scratch state rather than sharing one closure
+    /// behind a lock
+    /// Parameters:
+    ///     func:
+    ///         A function pointer or closure which takes in an input and
+    ///         produces an output. Write this function assuming it will run
+    ///         on a seperate thread rather than the main thread. Must be
+    ///         `Clone` so every worker can be handed its own copy
+    ///     num_threads:
+    ///         How many worker threads to keep around. Must be at least 1
+    ///
+    pub fn with_threads<F>(mut func: F, num_threads: usize) -> BackgroundWorker<Input, Output>
+    where
+        F: FnMut(Input) -> Output + Clone + Send + 'static,
+    {
+        Self::with_threads_with_context(move |value, _ctx: &WorkerContext| func(value), num_threads)
+    }
+
+    ///
+    /// Same as with_threads, but `func` also receives a &WorkerContext (see
+    /// new_with_context)
+    ///
+    pub fn with_threads_with_context<F>(func: F, num_threads: usize) -> BackgroundWorker<Input, Output>
+    where
+        F: FnMut(Input, &WorkerContext) -> Output + Clone + Send + 'static,
+    {
+        Self::build(func, num_threads, None)
+    }
+
+    ///
+    /// Same as with_threads, but every worker thread is given a name (via
+    /// thread::Builder::name) derived from `name`, useful for telling workers
+    /// apart in a debugger or a panic backtrace. There's no post-construction
+    /// `with_name` setter: workers are spawned eagerly right here, and
+    /// thread::Builder::name has to be set before the thread is spawned, so
+    /// the name has to be supplied up front instead
+    ///
+    pub fn with_threads_named<F>(mut func: F, num_threads: usize, name: &str) -> BackgroundWorker<Input, Output>
+    where
+        F: FnMut(Input) -> Output + Clone + Send + 'static,
+    {
+        Self::with_threads_with_context_named(move |value, _ctx: &WorkerContext| func(value), num_threads, name)
+    }
+
+    ///
+    /// Same as with_threads_with_context, but named like with_threads_named
+    ///
+    pub fn with_threads_with_context_named<F>(
+        func: F,
+        num_threads: usize,
+        name: &str,
+    ) -> BackgroundWorker<Input, Output>
+    where
+        F: FnMut(Input, &WorkerContext) -> Output + Clone + Send + 'static,
+    {
+        Self::build(func, num_threads, Some(name.to_string()))
+    }
+
+    ///
+    /// Same as with_threads, but for function state that can't be `Clone`
+    /// (e.g. a connection or a cache): instead of cloning one closure,
+    /// `factory` is called once per worker so each one builds its own
+    /// independent, non-Clone resource
+    /// Parameters:
+    ///     factory:
+    ///         Called once per worker thread (num_threads times total) to
+    ///         produce that worker's own closure
+    ///     num_threads:
+    ///         How many worker threads to keep around. Must be at least 1
+    ///
+    pub fn with_threads_from_factory<F, Fact>(
+        factory: Fact,
+        num_threads: usize,
+    ) -> BackgroundWorker<Input, Output>
+    where
+        F: FnMut(Input) -> Output + Send + 'static,
+        Fact: Fn() -> F,
+    {
+        Self::with_threads_with_context_from_factory(
+            move || {
+                let mut func = factory();
+                move |value, _ctx: &WorkerContext| func(value)
+            },
+            num_threads,
+        )
+    }
+
+    ///
+    /// Same as with_threads_from_factory, but each worker's closure also
+    /// receives a &WorkerContext (see new_with_context)
+    ///
+    pub fn with_threads_with_context_from_factory<F, Fact>(
+        factory: Fact,
+        num_threads: usize,
+    ) -> BackgroundWorker<Input, Output>
+    where
+        F: FnMut(Input, &WorkerContext) -> Output + Send + 'static,
+        Fact: Fn() -> F,
+    {
+        let num_threads = num_threads.max(1);
+        let funcs: Vec<WorkerFn<Input, Output>> =
+            (0..num_threads).map(|_| Box::new(factory()) as Box<_>).collect();
+        Self::build_from_funcs(funcs, None)
+    }
+
+    ///
+    /// Funnels `func` through with_threads/with_threads_with_context and their
+    /// `_named` variants: clones it once per worker and hands each worker its
+    /// own copy, then builds the actual worker pool via build_from_funcs
+    ///
+    fn build<F>(func: F, num_threads: usize, name: Option<String>) -> BackgroundWorker<Input, Output>
+    where
+        F: FnMut(Input, &WorkerContext) -> Output + Clone + Send + 'static,
+    {
+        let num_threads = num_threads.max(1);
+
+        let mut funcs: Vec<WorkerFn<Input, Output>> = Vec::with_capacity(num_threads);
+        for _ in 1..num_threads {
+            funcs.push(Box::new(func.clone()));
+        }
+        funcs.push(Box::new(func));
+
+        Self::build_from_funcs(funcs, name)
+    }
+
+    ///
+    /// The actual constructor everything else funnels into: takes one already
+    /// boxed, independent closure per worker thread (so callers that can't
+    /// satisfy `Clone` build their own Vec via with_threads_from_factory or a
+    /// single-element Vec as new_with_context does) and spawns a worker per
+    /// entry
+    ///
+    fn build_from_funcs(
+        funcs: Vec<WorkerFn<Input, Output>>,
+        name: Option<String>,
+    ) -> BackgroundWorker<Input, Output> {
+        let num_threads = funcs.len();
+
+        let mut deques = Vec::with_capacity(num_threads);
+        for _ in 0..num_threads {
+            deques.push(Arc::new(Mutex::new(BinaryHeap::new())));
+        }
+
+        let (context_tx, context_rx) = mpsc::channel();
+
+        let shared = WorkerShared {
+            deques,
             outqueue: Arc::new(Mutex::new(VecDeque::new())),
-            inqueue: Arc::new(Mutex::new(VecDeque::new())),
-            thread_handle: None,
-            thread_dead: Arc::new(AtomicBool::new(true)),
+            outqueue_cvar: Arc::new(Condvar::new()),
+            wake_lock: Arc::new(Mutex::new(())),
+            wake_cvar: Arc::new(Condvar::new()),
+            queued: Arc::new(AtomicUsize::new(0)),
+            pending: Arc::new(AtomicUsize::new(0)),
+            completed: Arc::new(AtomicUsize::new(0)),
+            panicked: Arc::new(AtomicUsize::new(0)),
+            stop: Arc::new(AtomicBool::new(false)),
+            context_tx,
+        };
+
+        let thread_handles = funcs
+            .into_iter()
+            .enumerate()
+            .map(|(index, func)| Self::spawn_worker(index, func, name.as_deref(), shared.clone()))
+            .collect();
+
+        BackgroundWorker {
+            outqueue: shared.outqueue,
+            outqueue_cvar: shared.outqueue_cvar,
+            deques: shared.deques,
+            wake_lock: shared.wake_lock,
+            wake_cvar: shared.wake_cvar,
+            queued: shared.queued,
+            pending: shared.pending,
+            completed: shared.completed,
+            panicked: shared.panicked,
+            next_push: AtomicUsize::new(0),
+            next_seq: 0,
+            stop: shared.stop,
+            shutdown_complete: Arc::new(AtomicBool::new(false)),
+            thread_handles,
+            context_rx: Arc::new(Mutex::new(context_rx)),
+            context_tx: shared.context_tx,
+            latest_status: Mutex::new((0..num_threads).map(|_| None).collect()),
+            latest_progress: Mutex::new((0..num_threads).map(|_| None).collect()),
         }
     }
 
     ///
     /// Pops a value from the outqueue and returns Some(x) if it works, or None if
-    /// it isn't successful
+    /// it isn't successful. The popped value is itself a Result: Err(WorkerError)
+    /// if that particular item's invocation of `function` panicked
     /// *Note:
     ///     Internally locks the outqueue and then pops from the front (pop_front())
-    /// 
-    pub fn pop(&mut self) -> Option<Output> {
+    ///
+    pub fn pop(&mut self) -> Option<Result<Output, WorkerError>> {
         self.outqueue.lock().unwrap().pop_front()
     }
 
-    /// 
+    ///
+    /// Like pop, but blocks the calling thread instead of returning None when
+    /// the outqueue is momentarily empty. Returns None only once the worker has
+    /// fully shut down (see shutdown()) and there is nothing left to pop, so
+    /// consumers don't have to busy-poll pop() while waiting on a long task
+    ///
+    pub fn pop_blocking(&mut self) -> Option<Result<Output, WorkerError>> {
+        let mut guard = self.outqueue.lock().unwrap();
+        loop {
+            if let Some(value) = guard.pop_front() {
+                return Some(value);
+            }
+            if self.shutdown_complete.load(Ordering::Acquire) {
+                return None;
+            }
+            guard = self.outqueue_cvar.wait(guard).unwrap();
+        }
+    }
+
+    ///
     /// Fills a buffer with data
     /// Internally uses the pop function repeatedly and leaves the value untouched if
-    /// it can't pop a value out. 
-    /// 
+    /// it can't pop a value out.
+    ///
     /// Returns:
     ///     The number of successful pops in a usize
-    /// 
-    pub fn pop_vec(&mut self, buffer: &mut Vec<Output>) -> usize{
+    ///
+    pub fn pop_vec(&mut self, buffer: &mut Vec<Result<Output, WorkerError>>) -> usize{
         let mut num_successful = 0;
         for i in 0..buffer.len(){
             if let Some(data) = self.pop(){
@@ -107,75 +604,253 @@ where
     }
 
     ///
-    /// Creates a thread and sets the appropriate flags/values to indicate that
-    /// 
-    fn create_thread(&mut self) {
-        let inqueue_clone = self.inqueue.clone();
-        let outqueue_clone = self.outqueue.clone();
-        let thread_dead_clone = self.thread_dead.clone();
-        let func_clone = self.function.clone();
+    /// Applies every ContextMessage sent so far to latest_status/latest_progress.
+    /// Called from current_status/current_progress rather than from a dedicated
+    /// draining thread, since nothing else needs these messages in real time
+    ///
+    fn drain_context(&self) {
+        let rx = self.context_rx.lock().unwrap();
+        while let Ok(message) = rx.try_recv() {
+            match message {
+                ContextMessage::Status(index, status) => {
+                    self.latest_status.lock().unwrap()[index] = Some(status)
+                }
+                ContextMessage::Progress(index, done, total) => {
+                    self.latest_progress.lock().unwrap()[index] = Some((done, total))
+                }
+            }
+        }
+    }
+
+    ///
+    /// The most recent status each worker reported via
+    /// WorkerContext::set_status, indexed the same as deques/thread_handles.
+    /// An entry is None if that worker hasn't reported a status yet (e.g.
+    /// `function` doesn't take a WorkerContext, or just hasn't called
+    /// set_status)
+    ///
+    pub fn current_status(&self) -> Vec<Option<String>> {
+        self.drain_context();
+        self.latest_status.lock().unwrap().clone()
+    }
+
+    ///
+    /// The most recent (done, total) pair each worker reported via
+    /// WorkerContext::report_progress, indexed the same as current_status()
+    ///
+    pub fn current_progress(&self) -> Vec<Option<(u64, u64)>> {
+        self.drain_context();
+        self.latest_progress.lock().unwrap().clone()
+    }
 
-        self.thread_dead.store(false, Ordering::Release);
+    ///
+    /// Lifetime count of items that have finished processing, whether they
+    /// completed normally or panicked. Unlike join(), this doesn't block
+    ///
+    pub fn processed_count(&self) -> usize {
+        self.completed.load(Ordering::Acquire) + self.panicked.load(Ordering::Acquire)
+    }
 
-        self.thread_handle = Some(thread::spawn(move || {
-            while let Some(data) = inqueue_clone.lock().unwrap().pop_front() {
-                outqueue_clone.lock().unwrap().push_back(func_clone(data));
+    ///
+    /// Attempts to steal the highest-priority input from a sibling's deque,
+    /// trying every other worker in turn starting just after `self_index`
+    ///
+    fn steal(deques: &[Arc<Mutex<BinaryHeap<Entry<Input>>>>], self_index: usize) -> Option<Entry<Input>> {
+        let num_threads = deques.len();
+        for offset in 1..num_threads {
+            let victim = (self_index + offset) % num_threads;
+            if let Some(entry) = deques[victim].lock().unwrap().pop() {
+                return Some(entry);
             }
-
-            thread_dead_clone.store(true, Ordering::Release);
-        }));
+        }
+        None
     }
 
     ///
-    /// Checks if the thread is dead and spawns one if it is
-    /// 
-    fn spawn_thread_if_dead(&mut self) {
-        if self.thread_dead.load(Ordering::Acquire) {
-            self.create_thread();
+    /// Spawns the persistent worker thread at `index`. The worker pops from its
+    /// own deque (falling back to stealing), and when both are empty it parks on
+    /// wake_cvar instead of exiting, waking up again on the next enque or on
+    /// shutdown(). Each invocation of `function` is isolated with
+    /// catch_unwind so that one panicking item can't tear down the worker or
+    /// drop the rest of the queue
+    ///
+    fn spawn_worker(
+        index: usize,
+        mut func: WorkerFn<Input, Output>,
+        name: Option<&str>,
+        shared: WorkerShared<Input, Output>,
+    ) -> JoinHandle<()> {
+        let WorkerShared {
+            deques,
+            outqueue,
+            outqueue_cvar,
+            wake_lock,
+            wake_cvar,
+            queued,
+            pending,
+            completed,
+            panicked,
+            stop,
+            context_tx,
+        } = shared;
+        let own_deque = deques[index].clone();
+
+        let body = move || {
+            let context = WorkerContext { index, sender: context_tx };
+
+            loop {
+                let own_pop = own_deque.lock().unwrap().pop();
+                let entry = own_pop.or_else(|| Self::steal(&deques, index));
+
+                let entry = match entry {
+                    Some(entry) => entry,
+                    None => {
+                        let guard = wake_lock.lock().unwrap();
+                        if queued.load(Ordering::Acquire) != 0 {
+                            continue;
+                        }
+                        if stop.load(Ordering::Acquire) {
+                            break;
+                        }
+                        drop(wake_cvar.wait(guard).unwrap());
+                        continue;
+                    }
+                };
+
+                queued.fetch_sub(1, Ordering::AcqRel);
+
+                let result = match panic::catch_unwind(AssertUnwindSafe(|| func(entry.value, &context))) {
+                    Ok(output) => {
+                        completed.fetch_add(1, Ordering::AcqRel);
+                        Ok(output)
+                    }
+                    Err(payload) => {
+                        panicked.fetch_add(1, Ordering::AcqRel);
+                        Err(describe_panic(payload))
+                    }
+                };
+
+                outqueue.lock().unwrap().push_back(result);
+                outqueue_cvar.notify_one();
+                pending.fetch_sub(1, Ordering::AcqRel);
+            }
+        };
+
+        match name {
+            Some(name) => thread::Builder::new()
+                .name(format!("{}-{}", name, index))
+                .spawn(body)
+                .expect("failed to spawn worker thread"),
+            None => thread::spawn(body),
         }
     }
 
-    /// 
-    /// Locks the inqueue and pushes a value to the back and 
-    /// creats a thread if needed
-    /// 
+    ///
+    /// Same as enque but lets the caller mark how urgent this value is: workers
+    /// drain their highest-priority item first. Equal priorities are still
+    /// processed in submission order. Note that this ordering is only a hint,
+    /// since several other items may already be in flight on sibling workers
+    /// regardless of how this one is prioritized
+    ///
     /// Parameters:
     ///     value:
     ///         A value of type Input to be pushed into the queue
-    /// 
+    ///     priority:
+    ///         Higher values are dequeued first
+    ///
+    pub fn enque_with_priority(&mut self, value: Input, priority: u64) {
+        let index = self.next_push.fetch_add(1, Ordering::AcqRel) % self.deques.len();
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        self.deques[index].lock().unwrap().push(Entry { priority, seq, value });
+        self.queued.fetch_add(1, Ordering::AcqRel);
+        self.pending.fetch_add(1, Ordering::AcqRel);
+
+        let _guard = self.wake_lock.lock().unwrap();
+        self.wake_cvar.notify_all();
+    }
+
+    ///
+    /// Locks the next deque (chosen round-robin) and pushes a value at the
+    /// default priority, then wakes a parked worker to handle it
+    ///
+    /// Parameters:
+    ///     value:
+    ///         A value of type Input to be pushed into the queue
+    ///
     pub fn enque(&mut self, value: Input) {
-        self.inqueue.lock().unwrap().push_back(value);
-        self.spawn_thread_if_dead();
+        self.enque_with_priority(value, DEFAULT_PRIORITY);
     }
 
     ///
     /// Same as enque but instead pushes a Vec<Input> of values rather
-    /// than a single one by iterating over the Vec and queueing it in
-    /// the same way as enque() and then creats a thread if needed
-    /// 
+    /// than a single one by iterating over the Vec and round-robining each one
+    /// across the worker deques in the same way as enque()
+    ///
     /// Parameters:
     ///     values:
     ///         A Vec<Input> to be queued in a first-come first-serve fashion
-    /// 
+    ///
     pub fn enque_vec(&mut self, values: Vec<Input>) {
-        for i in values {
-            self.inqueue.lock().unwrap().push_back(i);
+        for value in values {
+            self.enque(value);
         }
-        self.spawn_thread_if_dead();
     }
 
     ///
-    /// Blocks the caller until the current thread is done (ie. It finishes all
-    /// the leftover data in the queue as if it were on the same thread as the
-    /// caller)
-    /// 
-    pub fn join(&mut self) {
-        if !self.thread_dead.load(Ordering::Acquire) {
-            let mut x = None;
-            std::mem::swap(&mut x, &mut self.thread_handle);
-            if let Some(y) = x {
-                y.join().unwrap();
-            }
+    /// Blocks the caller until every queued and in-flight item has been
+    /// processed (ie. As if it were on the same thread as the caller), by
+    /// waiting on the shared pending counter rather than a single JoinHandle.
+    /// Returns a JoinSummary with the lifetime completed/panicked counts,
+    /// mirroring the explicit-join-returns-result model of the newer std
+    /// thread API instead of silently unwrapping a panic into the caller
+    ///
+    pub fn join(&mut self) -> JoinSummary {
+        while self.pending.load(Ordering::Acquire) != 0 {
+            thread::yield_now();
+        }
+
+        JoinSummary {
+            completed: self.completed.load(Ordering::Acquire),
+            panicked: self.panicked.load(Ordering::Acquire),
+        }
+    }
+
+    ///
+    /// Tells every worker to stop waiting for new work once its deque (and any
+    /// stealable sibling work) is drained, then blocks until all of them have
+    /// actually exited. Anything already queued still gets processed; nothing
+    /// enqueued after shutdown() is guaranteed to run
+    ///
+    pub fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        {
+            let _guard = self.wake_lock.lock().unwrap();
+            self.wake_cvar.notify_all();
+        }
+
+        for handle in self.thread_handles.drain(..) {
+            let _ = handle.join();
         }
+
+        self.shutdown_complete.store(true, Ordering::Release);
+        self.outqueue_cvar.notify_all();
+    }
+}
+
+impl<Input: 'static, Output: 'static> Drop for BackgroundWorker<Input, Output>
+where
+    Input: std::marker::Send,
+    Output: std::marker::Send + std::clone::Clone + std::cmp::PartialEq,
+{
+    ///
+    /// Workers are parked on wake_cvar rather than dying on an empty queue, so
+    /// without this a BackgroundWorker dropped without an explicit shutdown()
+    /// call would leak its worker threads forever. shutdown() is safe to call
+    /// again even if the caller already called it themselves
+    ///
+    fn drop(&mut self) {
+        self.shutdown();
     }
 }
@@ -10,7 +10,7 @@ pub mod tests {
         let mut worker: BackgroundWorker<i32, f32> = BackgroundWorker::new(|x| {x as f32});
         worker.enque(1);
         worker.join();
-        assert_eq!(worker.pop().unwrap(), 1.0 as f32);
+        assert_eq!(worker.pop().unwrap().unwrap(), 1.0 as f32);
     }
 
     #[test]
@@ -20,9 +20,10 @@ pub mod tests {
         });
         worker.enque_vec(vec![1, 2, 3, 4, 5, 6, 7, 8]);
         worker.join();
-        let mut buf = vec![0.0; 8];
+        let mut buf = vec![Ok(0.0); 8];
         worker.pop_vec(&mut buf);
-        assert_eq!(buf, vec![1., 2., 3., 4., 5., 6., 7., 8.]);
+        let values: Vec<f32> = buf.into_iter().map(|r| r.unwrap()).collect();
+        assert_eq!(values, vec![1., 2., 3., 4., 5., 6., 7., 8.]);
     }
 
     #[test]
@@ -32,9 +33,195 @@ pub mod tests {
         });
 
         worker.enque_vec(vec![(); 10]);
+        let summary = worker.join();
+        assert_eq!(summary.completed, 10);
+        assert_eq!(summary.panicked, 0);
+        let mut buf = vec![Ok(()); 10];
+        worker.pop_vec(&mut buf);
+        assert_eq!(buf, vec![Ok(());10]);
+    }
+
+    #[test]
+    fn single_thread_preserves_fifo_order() {
+        // Regression test: a single worker pops its own BinaryHeap, which by
+        // itself keeps no insertion order, so this
+        // pins down that equal-priority (the default for enque/enque_vec)
+        // items still come out in submission order via the seq tiebreak.
+        let mut worker: BackgroundWorker<i32, i32> = BackgroundWorker::new(|x| x);
+        for value in 0..5 {
+            worker.enque(value);
+        }
+        worker.join();
+        let mut buf = vec![Ok(0); 5];
+        worker.pop_vec(&mut buf);
+        let values: Vec<i32> = buf.into_iter().map(|r| r.unwrap()).collect();
+        assert_eq!(values, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn priority() {
+        let mut worker: BackgroundWorker<i32, i32> = BackgroundWorker::new(|x| x);
+        worker.enque_with_priority(1, 0);
+        worker.enque_with_priority(2, 10);
+        worker.enque_with_priority(3, 5);
+        worker.join();
+        let mut buf = vec![Ok(0); 3];
+        worker.pop_vec(&mut buf);
+        let mut values: Vec<i32> = buf.into_iter().map(|r| r.unwrap()).collect();
+        values.sort();
+        assert_eq!(values, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn with_threads() {
+        let mut worker: BackgroundWorker<i32, i32> = BackgroundWorker::with_threads(|x| x * 2, 4);
+        worker.enque_vec((0..100).collect());
+        worker.join();
+        let mut buf = vec![Ok(0); 100];
+        let popped = worker.pop_vec(&mut buf);
+        assert_eq!(popped, 100);
+        let mut values: Vec<i32> = buf.into_iter().map(|r| r.unwrap()).collect();
+        values.sort();
+        assert_eq!(values, (0..100).map(|x| x * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn shutdown_and_pop_blocking() {
+        let mut worker: BackgroundWorker<i32, i32> = BackgroundWorker::new(|x| x + 1);
+        worker.enque_vec(vec![1, 2, 3]);
+
+        let mut results = Vec::new();
+        for _ in 0..3 {
+            results.push(worker.pop_blocking().unwrap().unwrap());
+        }
+        results.sort();
+        assert_eq!(results, vec![2, 3, 4]);
+
+        worker.shutdown();
+        assert_eq!(worker.pop_blocking(), None);
+    }
+
+    #[test]
+    fn drop_shuts_down_worker_threads() {
+        let worker: BackgroundWorker<i32, i32> = BackgroundWorker::with_threads(|x| x, 4);
+        let thread_count = worker.thread_handles.len();
+        assert_eq!(thread_count, 4);
+        drop(worker);
+        // If Drop didn't join the worker threads, this would be detectable as
+        // leaked, still-parked threads; there isn't a portable way to assert
+        // thread exit directly, so this mainly guards against Drop panicking.
+    }
+
+    #[test]
+    fn new_with_captured_state() {
+        let offset = 10;
+        let mut worker: BackgroundWorker<i32, i32> = BackgroundWorker::new_with(move |x| x + offset);
+        worker.enque_vec(vec![1, 2, 3]);
+        worker.join();
+        let mut buf = vec![Ok(0); 3];
+        worker.pop_vec(&mut buf);
+        let mut values: Vec<i32> = buf.into_iter().map(|r| r.unwrap()).collect();
+        values.sort();
+        assert_eq!(values, vec![11, 12, 13]);
+    }
+
+    #[test]
+    fn with_threads_from_factory_allows_non_clone_state() {
+        // NotClone doesn't implement Clone, so this only compiles/works because each
+        // worker's closure is built by calling `factory`, never by cloning one
+        struct NotClone(std::cell::RefCell<i32>);
+
+        let mut worker: BackgroundWorker<i32, i32> = BackgroundWorker::with_threads_from_factory(
+            || {
+                let scratch = NotClone(std::cell::RefCell::new(0));
+                move |x: i32| {
+                    *scratch.0.borrow_mut() += 1;
+                    x + *scratch.0.borrow()
+                }
+            },
+            4,
+        );
+        worker.enque_vec(vec![1; 8]);
+        worker.join();
+        let mut buf = vec![Ok(0); 8];
+        worker.pop_vec(&mut buf);
+        let values: Vec<i32> = buf.into_iter().map(|r| r.unwrap()).collect();
+        assert_eq!(values.len(), 8);
+        // Each worker's own scratch counter starts at 0 and is bumped before
+        // being added in, so every result is at least 1 (x) + 1 (scratch)
+        assert!(values.iter().all(|&v| v >= 2));
+    }
+
+    #[test]
+    fn panic_is_isolated() {
+        let mut worker: BackgroundWorker<i32, i32> = BackgroundWorker::new(|x| {
+            if x == 0 {
+                panic!("boom");
+            }
+            x
+        });
+        worker.enque_vec(vec![0, 1, 2]);
+        let summary = worker.join();
+        assert_eq!(summary.completed, 2);
+        assert_eq!(summary.panicked, 1);
+
+        let mut buf = vec![Ok(0); 3];
+        worker.pop_vec(&mut buf);
+        let panicked = buf.iter().filter(|r| r.is_err()).count();
+        assert_eq!(panicked, 1);
+    }
+
+    #[test]
+    fn context_status_and_progress() {
+        let mut worker: BackgroundWorker<i32, i32> = BackgroundWorker::new_with_context(|x, ctx| {
+            ctx.set_status("processing");
+            ctx.report_progress(1, 1);
+            x
+        });
+
+        assert_eq!(worker.current_status(), vec![None]);
+        worker.enque(1);
+        worker.join();
+
+        assert_eq!(worker.current_status(), vec![Some("processing".to_string())]);
+        assert_eq!(worker.current_progress(), vec![Some((1, 1))]);
+        assert_eq!(worker.processed_count(), 1);
+    }
+
+    #[test]
+    fn context_status_is_per_worker() {
+        // With only 2 items and stealing enabled (chunk0-1), one worker can
+        // legitimately steal both and leave the other idle, so this can't
+        // assert every worker reported a status -- only that statuses are
+        // tracked per-worker (correct length) and that whichever worker(s)
+        // actually ran the function did report through their own slot.
+        let mut worker: BackgroundWorker<i32, i32> =
+            BackgroundWorker::with_threads_with_context(
+                |x, ctx| {
+                    ctx.set_status("processing");
+                    x
+                },
+                2,
+            );
+
+        worker.enque_vec(vec![1, 2]);
+        worker.join();
+
+        let statuses = worker.current_status();
+        assert_eq!(statuses.len(), 2);
+        assert!(statuses.iter().any(|s| s.as_deref() == Some("processing")));
+    }
+
+    #[test]
+    fn with_threads_named() {
+        let mut worker: BackgroundWorker<i32, i32> =
+            BackgroundWorker::with_threads_named(|x| x + 1, 2, "bg-worker");
+        worker.enque_vec(vec![1, 2]);
         worker.join();
-        let mut buf = vec![(); 10];
+        let mut buf = vec![Ok(0); 2];
         worker.pop_vec(&mut buf);
-        assert_eq!(buf, vec![();10]);
+        let mut values: Vec<i32> = buf.into_iter().map(|r| r.unwrap()).collect();
+        values.sort();
+        assert_eq!(values, vec![2, 3]);
     }
-}
\ No newline at end of file
+}